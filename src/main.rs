@@ -1,3 +1,70 @@
-fn main() {
+mod plugin_udp;
+
+use std::process::ExitCode;
+
+const USAGE: &str =
+    "usage: restrike-vta [--rewrite-rules <rules.txt> | --validate-protocol <schema.txt>]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [] => start(&[]),
+        [flag, path] if flag == "--rewrite-rules" => {
+            match std::fs::read_to_string(path)
+                .map_err(plugin_udp::UdpError::from)
+                .and_then(|content| plugin_udp::parse_rewrite_rules(&content))
+            {
+                Ok(rules) => {
+                    println!("Loaded {} rewrite rules from {}", rules.len(), path);
+                    start(&rules)
+                }
+                Err(e) => {
+                    eprintln!("{}: {}", path, e);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        [flag, path] if flag == "--validate-protocol" => validate_protocol(path),
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn start(rewrite_rules: &[plugin_udp::RewriteRule]) -> ExitCode {
     println!("reStrike VTA backend starting...");
+    plugin_udp::start_udp_server(rewrite_rules);
+    ExitCode::SUCCESS
+}
+
+/// Dry-run a protocol schema file: list its streams, or print every warning
+/// and fail if any were found.
+fn validate_protocol(path: &str) -> ExitCode {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("{}: {}", path, plugin_udp::UdpError::from(e));
+            return ExitCode::FAILURE;
+        }
+    };
+    match plugin_udp::load_protocol_definitions(&content, plugin_udp::ValidationMode::Lenient) {
+        Ok((definitions, warnings)) if warnings.is_empty() => {
+            for def in definitions {
+                println!("{}: {}", def.name, def.main_streams.join(", "));
+            }
+            println!("{}: OK", path);
+            ExitCode::SUCCESS
+        }
+        Ok((_, warnings)) => {
+            for warning in &warnings {
+                println!("{}: {}", path, warning);
+            }
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            ExitCode::FAILURE
+        }
+    }
 }
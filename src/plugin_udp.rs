@@ -1,4 +1,1113 @@
 // UDP ingestion plugin stub
-pub fn start_udp_server() {
-    // TODO: Implement UDP server logic
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::thread;
+use std::time::Duration;
+
+pub fn start_udp_server(_rewrite_rules: &[RewriteRule]) {
+    // TODO: Implement UDP server logic, applying the rewrite rules to each
+    // parsed datagram before dispatch
+}
+
+// Items marked `#[allow(dead_code)]` below have no caller outside the tests
+// until the listener above lands; drop the attribute once it calls them.
+
+/// A single PSS datagram split into its stream code and arguments.
+#[derive(Debug, Clone, PartialEq)]
+#[allow(dead_code)]
+pub struct UdpMessage {
+    pub stream: String,
+    pub arguments: Vec<String>,
+}
+
+impl UdpMessage {
+    /// Serialize back to the canonical `stream;arg1;arg2;` wire form.
+    #[allow(dead_code)]
+    pub fn to_wire(&self) -> String {
+        let mut wire = format!("{};", self.stream);
+        for arg in &self.arguments {
+            wire.push_str(arg);
+            wire.push(';');
+        }
+        wire
+    }
+}
+
+/// Send a message back to the PSS console (e.g. a resend request).
+///
+/// Fields must be unpadded and free of `;`. Empty arguments are sent as-is
+/// so positional streams keep their layout (`at1;N. D;;MRN;`), but the stream
+/// and the last argument must be non-empty: a trailing blank would be
+/// indistinguishable from the closing `;`.
+#[allow(dead_code)]
+pub fn send_udp_message(
+    socket: &UdpSocket,
+    addr: impl ToSocketAddrs,
+    message: &UdpMessage,
+) -> Result<usize, UdpError> {
+    let last_is_empty = message.arguments.last().is_some_and(|arg| arg.is_empty());
+    if message.stream.is_empty() || last_is_empty {
+        return Err(UdpError::Protocol(format!(
+            "cannot send {} message with an empty stream or last argument",
+            message.stream
+        )));
+    }
+    if let Some(field) = std::iter::once(&message.stream)
+        .chain(&message.arguments)
+        .find(|field| field.trim() != *field || field.contains(';'))
+    {
+        return Err(UdpError::Protocol(format!(
+            "cannot send field '{}' in {} message",
+            field, message.stream
+        )));
+    }
+    Ok(socket.send_to(message.to_wire().as_bytes(), addr)?)
+}
+
+/// Bind the listener socket, retrying while a previous process still holds
+/// the port after a restart.
+///
+/// Makes up to `attempts` binds, sleeping `delay` between them, and logs each
+/// failure. `SO_REUSEADDR` is not exposed by `std`, so the retry is the only
+/// way to ride out the port being briefly taken.
+#[allow(dead_code)]
+pub fn bind_with_retry(
+    addr: impl ToSocketAddrs + Copy + fmt::Debug,
+    attempts: u32,
+    delay: Duration,
+) -> Result<UdpSocket, UdpError> {
+    let mut attempt = 1;
+    loop {
+        match UdpSocket::bind(addr) {
+            Ok(socket) => return Ok(socket),
+            Err(e) if attempt < attempts => {
+                eprintln!(
+                    "bind {:?} attempt {}/{} failed: {}",
+                    addr, attempt, attempts, e
+                );
+                thread::sleep(delay);
+                attempt += 1;
+            }
+            Err(e) => {
+                eprintln!(
+                    "bind {:?} attempt {}/{} failed: {}",
+                    addr, attempt, attempts, e
+                );
+                return Err(e.into());
+            }
+        }
+    }
+}
+
+/// Parse a raw PSS datagram.
+///
+/// The text is normalized first so argument indices do not depend on the
+/// console's punctuation: each field is trimmed and empty fields are dropped.
+/// The canonical form is `stream;arg1;arg2`, so `pt1;3;`, `pt1;3`, `pt1;;3;`
+/// and `;pt1;3;` all yield stream `pt1` with arguments `["3"]`.
+///
+/// Dropping empty fields also shifts positional streams: `at1;N. D;;MRN`
+/// yields `["N. D", "MRN"]`, so handlers must not rely on a blank field
+/// holding its index.
+#[allow(dead_code)]
+pub fn parse_udp_message(raw: &str) -> Result<UdpMessage, UdpError> {
+    let mut fields = raw
+        .split(';')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(str::to_string);
+    let stream = fields
+        .next()
+        .ok_or_else(|| UdpError::Protocol(format!("empty message: '{}'", raw.trim())))?;
+    Ok(UdpMessage {
+        stream,
+        arguments: fields.collect(),
+    })
+}
+
+/// Renames an off-spec stream code before dispatch, e.g. `PT1 -> pt1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewriteRule {
+    pub pattern: String,
+    pub replacement: String,
+}
+
+/// Parse rewrite rules from `pattern -> replacement` lines. Blank lines and
+/// `#` comments are ignored; order is preserved. A line must contain exactly
+/// one `->`.
+pub fn parse_rewrite_rules(content: &str) -> Result<Vec<RewriteRule>, UdpError> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (pattern, replacement) = line
+                .split_once("->")
+                .map(|(p, r)| (p.trim(), r.trim()))
+                .filter(|(p, r)| !p.is_empty() && !r.is_empty() && !r.contains("->"))
+                .ok_or_else(|| UdpError::Protocol(format!("invalid rewrite rule: '{}'", line)))?;
+            Ok(RewriteRule {
+                pattern: pattern.to_string(),
+                replacement: replacement.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Rewrite the stream with the first rule whose pattern matches it exactly.
+/// At most one rule is applied, so a replacement is never rewritten again.
+#[allow(dead_code)]
+pub fn apply_rewrite_rules(mut message: UdpMessage, rules: &[RewriteRule]) -> UdpMessage {
+    if let Some(rule) = rules.iter().find(|rule| rule.pattern == message.stream) {
+        message.stream = rule.replacement.clone();
+    }
+    message
+}
+
+/// Point type carried by the `pt1`/`pt2` streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum PointType {
+    Punch,
+    Body,
+    Head,
+    TechnicalBody,
+    TechnicalHead,
+}
+
+/// Parse a `pt1;3;` datagram into the scoring athlete (1 or 2) and point type.
+///
+/// A point stream without a type (`pt1;`) is an error rather than being
+/// ignored, so the caller can surface or count it.
+#[allow(dead_code)]
+pub fn parse_points(message: &UdpMessage) -> Result<(u8, PointType), UdpError> {
+    let athlete = match message.stream.as_str() {
+        "pt1" => 1,
+        "pt2" => 2,
+        other => {
+            return Err(UdpError::Protocol(format!(
+                "expected pt1/pt2 stream, got '{}'",
+                other
+            )))
+        }
+    };
+    let point_type = match message.arguments.first().map(String::as_str) {
+        Some("1") => PointType::Punch,
+        Some("2") => PointType::Body,
+        Some("3") => PointType::Head,
+        Some("4") => PointType::TechnicalBody,
+        Some("5") => PointType::TechnicalHead,
+        Some(other) if !other.is_empty() => {
+            return Err(UdpError::Protocol(format!(
+                "{}: unknown point type '{}'",
+                message.stream, other
+            )))
+        }
+        _ => {
+            return Err(UdpError::Protocol(format!(
+                "{}: missing point type",
+                message.stream
+            )))
+        }
+    };
+    Ok((athlete, point_type))
+}
+
+/// Winner of one period as reported by the `wrd` stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct RoundWinner {
+    pub round: u8,
+    /// Athlete number (1 or 2), or `None` while the round is not decided.
+    pub winner: Option<u8>,
+}
+
+/// Parse a `wrd;rd1;2;rd2;1;rd3;0` payload into per-round winners.
+///
+/// PSS normally sends all three periods with `0` for undecided ones, but
+/// shorter payloads are accepted and yield only the rounds present. An empty
+/// payload or a round listed twice is an error.
+#[allow(dead_code)]
+pub fn parse_winner_rounds(message: &UdpMessage) -> Result<Vec<RoundWinner>, UdpError> {
+    if message.stream != "wrd" {
+        return Err(UdpError::Protocol(format!(
+            "expected wrd stream, got '{}'",
+            message.stream
+        )));
+    }
+    let args = &message.arguments;
+    if args.is_empty() {
+        return Err(UdpError::Protocol("wrd payload is empty".to_string()));
+    }
+    if !args.len().is_multiple_of(2) {
+        return Err(UdpError::Protocol(format!(
+            "wrd payload has an unpaired entry: {:?}",
+            args
+        )));
+    }
+
+    let mut seen_rounds = HashSet::new();
+    args.chunks(2)
+        .map(|pair| {
+            let round = pair[0]
+                .strip_prefix("rd")
+                .and_then(|n| n.parse::<u8>().ok())
+                .filter(|n| *n > 0)
+                .ok_or_else(|| UdpError::Protocol(format!("invalid wrd round '{}'", pair[0])))?;
+            if !seen_rounds.insert(round) {
+                return Err(UdpError::Protocol(format!(
+                    "wrd payload repeats round {}",
+                    round
+                )));
+            }
+            let winner = match pair[1].as_str() {
+                "0" => None,
+                "1" => Some(1),
+                "2" => Some(2),
+                other => {
+                    return Err(UdpError::Protocol(format!(
+                        "invalid winner '{}' for round {}",
+                        other, round
+                    )))
+                }
+            };
+            Ok(RoundWinner { round, winner })
+        })
+        .collect()
+}
+
+/// Encoding used by a PSS source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[allow(dead_code)]
+pub enum WireFormat {
+    /// `stream;arg;` UTF-8 text.
+    #[default]
+    Text,
+    /// Compact frames produced by [`encode_binary`], for high-rate links.
+    Binary,
+}
+
+/// Decode a datagram according to the format configured for its source.
+#[allow(dead_code)]
+pub fn decode_datagram(bytes: &[u8], format: WireFormat) -> Result<UdpMessage, UdpError> {
+    match format {
+        WireFormat::Text => {
+            let raw = std::str::from_utf8(bytes)
+                .map_err(|e| UdpError::Protocol(format!("invalid UTF-8: {}", e)))?;
+            parse_udp_message(raw)
+        }
+        WireFormat::Binary => decode_binary(bytes),
+    }
+}
+
+/// Stream codes addressable in binary frames; the id is the index.
+#[allow(dead_code)]
+const BINARY_STREAMS: &[&str] = &[
+    "pt1", "pt2", "hl1", "hl2", "wg1", "wg2", "ij0", "ij1", "ij2", "ch0", "ch1", "ch2", "brk",
+    "wrd", "wmh", "win", "clk", "rnd", "sc1", "sc2", "s11", "s21", "s12", "s22", "s13", "s23",
+    "avt", "pre", "rdy", "at1", "at2", "mch",
+];
+
+#[allow(dead_code)]
+const TAG_INT: u8 = 0x01;
+#[allow(dead_code)]
+const TAG_TEXT: u8 = 0x02;
+
+/// Encode a message as `[len: u16 BE][stream id: u8][args...]`, where each
+/// argument is either `0x01` + `i32 BE` or `0x02` + `u8 len` + UTF-8 bytes.
+/// `len` counts the bytes after the prefix.
+#[allow(dead_code)]
+pub fn encode_binary(message: &UdpMessage) -> Result<Vec<u8>, UdpError> {
+    let id = BINARY_STREAMS
+        .iter()
+        .position(|code| *code == message.stream)
+        .ok_or_else(|| {
+            UdpError::Protocol(format!("stream '{}' has no binary id", message.stream))
+        })?;
+
+    let mut body = vec![id as u8];
+    for arg in &message.arguments {
+        match arg.parse::<i32>() {
+            // Only digits that print back identically, so "03" stays text.
+            Ok(n) if n.to_string() == *arg => {
+                body.push(TAG_INT);
+                body.extend_from_slice(&n.to_be_bytes());
+            }
+            _ => {
+                let len = u8::try_from(arg.len()).map_err(|_| {
+                    UdpError::Protocol(format!("argument too long for binary frame: '{}'", arg))
+                })?;
+                body.push(TAG_TEXT);
+                body.push(len);
+                body.extend_from_slice(arg.as_bytes());
+            }
+        }
+    }
+
+    let len = u16::try_from(body.len())
+        .map_err(|_| UdpError::Protocol("binary frame too long".to_string()))?;
+    let mut frame = len.to_be_bytes().to_vec();
+    frame.extend(body);
+    Ok(frame)
+}
+
+/// Decode a frame produced by [`encode_binary`].
+#[allow(dead_code)]
+pub fn decode_binary(bytes: &[u8]) -> Result<UdpMessage, UdpError> {
+    let truncated = || UdpError::Protocol("truncated binary frame".to_string());
+    let (prefix, body) = bytes.split_first_chunk::<2>().ok_or_else(truncated)?;
+    if usize::from(u16::from_be_bytes(*prefix)) != body.len() {
+        return Err(UdpError::Protocol(format!(
+            "binary frame length mismatch: header {}, actual {}",
+            u16::from_be_bytes(*prefix),
+            body.len()
+        )));
+    }
+
+    let (&id, mut rest) = body.split_first().ok_or_else(truncated)?;
+    let stream = BINARY_STREAMS
+        .get(usize::from(id))
+        .ok_or_else(|| UdpError::Protocol(format!("unknown binary stream id {}", id)))?;
+
+    let mut arguments = Vec::new();
+    while let Some((&tag, tail)) = rest.split_first() {
+        match tag {
+            TAG_INT => {
+                let (value, tail) = tail.split_first_chunk::<4>().ok_or_else(truncated)?;
+                arguments.push(i32::from_be_bytes(*value).to_string());
+                rest = tail;
+            }
+            TAG_TEXT => {
+                let (&len, tail) = tail.split_first().ok_or_else(truncated)?;
+                if tail.len() < usize::from(len) {
+                    return Err(truncated());
+                }
+                let (text, tail) = tail.split_at(usize::from(len));
+                let text = std::str::from_utf8(text)
+                    .map_err(|e| UdpError::Protocol(format!("invalid UTF-8: {}", e)))?;
+                arguments.push(text.to_string());
+                rest = tail;
+            }
+            _ => {
+                return Err(UdpError::Protocol(format!(
+                    "unknown binary argument tag 0x{:02x}",
+                    tag
+                )))
+            }
+        }
+    }
+
+    Ok(UdpMessage {
+        stream: stream.to_string(),
+        arguments,
+    })
+}
+
+/// Errors raised by the UDP plugin while loading the protocol schema or
+/// parsing datagrams.
+#[derive(Debug)]
+pub enum UdpError {
+    Io(std::io::Error),
+    Protocol(String),
+}
+
+impl fmt::Display for UdpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UdpError::Io(e) => write!(f, "I/O error: {}", e),
+            UdpError::Protocol(msg) => write!(f, "protocol error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for UdpError {}
+
+impl From<std::io::Error> for UdpError {
+    fn from(e: std::io::Error) -> Self {
+        UdpError::Io(e)
+    }
+}
+
+/// One stream section of the PSS TXT schema (e.g. `# POINTS`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProtocolDefinition {
+    pub name: String,
+    pub main_streams: Vec<String>,
+    pub required_arguments: Vec<String>,
+    pub optional_arguments: Vec<String>,
+    pub examples: Vec<String>,
+    /// Stream code -> description text after the `;` in `MAIN_STREAMS`.
+    pub stream_descriptions: HashMap<String, String>,
+    /// Argument code -> description from `REQUIRED_ARGUMENTS`. Kept apart from
+    /// the optional ones because both lists may reuse a code (e.g. `ch0`'s `1`).
+    pub required_argument_descriptions: HashMap<String, String>,
+    /// Argument code -> description from `OPTIONAL_ARGUMENTS`.
+    pub optional_argument_descriptions: HashMap<String, String>,
+}
+
+/// A problem found in a schema section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    pub section: String,
+    pub kind: WarningKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WarningKind {
+    /// The stream code is already declared, earlier in the schema or in the
+    /// same section.
+    DuplicateStream(String),
+    /// The section has a title but no content.
+    EmptySection,
+    /// The section declares arguments or examples but no `MAIN_STREAMS`.
+    MissingMainStreams,
+    /// A `LABEL:` line names no known block; its entries are ignored.
+    UnknownBlock(String),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            WarningKind::DuplicateStream(code) => {
+                write!(f, "[{}] duplicate stream key '{}'", self.section, code)
+            }
+            WarningKind::EmptySection => write!(f, "[{}] empty definition", self.section),
+            WarningKind::MissingMainStreams => write!(f, "[{}] missing MAIN_STREAMS", self.section),
+            WarningKind::UnknownBlock(label) => {
+                write!(f, "[{}] unknown block '{}:'", self.section, label)
+            }
+        }
+    }
+}
+
+/// How the loader treats schema problems.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Skip bad sections and keep going; never fail a live event over them.
+    #[default]
+    Lenient,
+    /// Fail on the first bad section, for use while setting up a schema.
+    Strict,
+}
+
+/// Load the stream definitions along with the warnings for anything skipped.
+///
+/// In `Lenient` mode empty sections and sections without `MAIN_STREAMS` are
+/// dropped and counted in the returned warnings, and a duplicated stream stays
+/// with the section that declared it first. Loading still fails if no
+/// definition survives. In `Strict` mode any warning is an error.
+pub fn load_protocol_definitions(
+    content: &str,
+    mode: ValidationMode,
+) -> Result<(Vec<ProtocolDefinition>, Vec<Warning>), UdpError> {
+    let (definitions, warnings) = parse_protocol(content)?;
+    if mode == ValidationMode::Strict {
+        if let Some(warning) = warnings.first() {
+            return Err(UdpError::Protocol(warning.to_string()));
+        }
+    }
+    if definitions.is_empty() {
+        let skipped: Vec<String> = warnings.iter().map(Warning::to_string).collect();
+        return Err(UdpError::Protocol(format!(
+            "no stream definitions loaded; skipped: {}",
+            skipped.join(", ")
+        )));
+    }
+    Ok((definitions, warnings))
+}
+
+/// Parse the schema without touching any running server and report every
+/// problem instead of skipping the offending sections.
+#[allow(dead_code)]
+pub fn validate_protocol_definitions(content: &str) -> Result<Vec<Warning>, UdpError> {
+    let (_, warnings) = parse_protocol(content)?;
+    Ok(warnings)
+}
+
+fn parse_protocol(content: &str) -> Result<(Vec<ProtocolDefinition>, Vec<Warning>), UdpError> {
+    let mut definitions = Vec::new();
+    let mut warnings = Vec::new();
+    let mut seen_streams = HashSet::new();
+
+    let mut sections = vec![Vec::new()];
+    for line in content.lines() {
+        if line.trim() == "---" {
+            sections.push(Vec::new());
+        } else if let Some(section) = sections.last_mut() {
+            section.push(line);
+        }
+    }
+    // A leading comment-only block is the file preamble (title, version),
+    // not an empty section.
+    if is_preamble(&sections[0]) {
+        sections.remove(0);
+    }
+
+    for section in &sections {
+        if let Some(mut def) = parse_section(section, &mut warnings) {
+            // A duplicated stream stays with the section that declared it first.
+            let name = def.name.clone();
+            let mut duplicates = Vec::new();
+            def.main_streams.retain(|code| {
+                let first = seen_streams.insert(code.clone());
+                if !first {
+                    duplicates.push(code.clone());
+                }
+                first
+            });
+            for code in duplicates {
+                // A repeat within this section keeps its first description.
+                if !def.main_streams.contains(&code) {
+                    def.stream_descriptions.remove(&code);
+                }
+                warnings.push(Warning {
+                    section: name.clone(),
+                    kind: WarningKind::DuplicateStream(code),
+                });
+            }
+            if !def.main_streams.is_empty() {
+                definitions.push(def);
+            }
+        }
+    }
+
+    if definitions.is_empty() && warnings.is_empty() {
+        return Err(UdpError::Protocol(
+            "no stream definitions found".to_string(),
+        ));
+    }
+    Ok((definitions, warnings))
+}
+
+fn is_preamble(lines: &[&str]) -> bool {
+    lines
+        .iter()
+        .map(|line| line.trim())
+        .all(|line| line.is_empty() || line.starts_with('#'))
+}
+
+/// Returns `None` for sections that are not stream definitions (blank tail,
+/// documentation such as the example match flow) or that only produced a warning.
+fn parse_section(section: &[&str], warnings: &mut Vec<Warning>) -> Option<ProtocolDefinition> {
+    let mut def = ProtocolDefinition::default();
+    let mut block: Option<&str> = None;
+    let mut has_blocks = false;
+    let mut has_body = false;
+    let mut unknown_blocks = Vec::new();
+
+    for line in section {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some(comment) = trimmed.strip_prefix('#') {
+            if def.name.is_empty() {
+                def.name = comment.trim().to_string();
+            }
+            continue;
+        }
+        has_body = true;
+        if let Some(label) = trimmed.strip_suffix(':') {
+            block = Some(label);
+            if matches!(
+                label,
+                "MAIN_STREAMS" | "REQUIRED_ARGUMENTS" | "OPTIONAL_ARGUMENTS" | "EXAMPLES"
+            ) {
+                has_blocks = true;
+            } else if !unknown_blocks.contains(&label) {
+                unknown_blocks.push(label);
+            }
+            continue;
+        }
+
+        let (code, description) = match trimmed.split_once(';') {
+            Some((code, description)) => (code.trim().to_string(), description.trim().to_string()),
+            None => (trimmed.to_string(), String::new()),
+        };
+        match block {
+            // The first description of a code within a block wins.
+            Some("MAIN_STREAMS") => {
+                def.stream_descriptions
+                    .entry(code.clone())
+                    .or_insert(description);
+                def.main_streams.push(code);
+            }
+            Some("REQUIRED_ARGUMENTS") => {
+                def.required_argument_descriptions
+                    .entry(code.clone())
+                    .or_insert(description);
+                def.required_arguments.push(code);
+            }
+            Some("OPTIONAL_ARGUMENTS") => {
+                def.optional_argument_descriptions
+                    .entry(code.clone())
+                    .or_insert(description);
+                def.optional_arguments.push(code);
+            }
+            Some("EXAMPLES") => def.examples.push(trimmed.to_string()),
+            _ => {}
+        }
+    }
+
+    if def.name.is_empty() && !has_body {
+        return None;
+    }
+    if def.name.is_empty() {
+        def.name = "<unnamed>".to_string();
+    }
+    if !has_body {
+        warnings.push(Warning {
+            section: def.name,
+            kind: WarningKind::EmptySection,
+        });
+        return None;
+    }
+    for label in unknown_blocks {
+        warnings.push(Warning {
+            section: def.name.clone(),
+            kind: WarningKind::UnknownBlock(label.to_string()),
+        });
+    }
+    // Prose sections such as EXAMPLE MATCH FLOW carry no blocks at all.
+    if !has_blocks {
+        return None;
+    }
+    if def.main_streams.is_empty() {
+        warnings.push(Warning {
+            section: def.name,
+            kind: WarningKind::MissingMainStreams,
+        });
+        return None;
+    }
+    Some(def)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_SCHEMA: &str = include_str!("../protocol/pss_schema.txt");
+
+    fn message(stream: &str, arguments: &[&str]) -> UdpMessage {
+        UdpMessage {
+            stream: stream.to_string(),
+            arguments: arguments.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn sample_schema_validates_without_warnings() {
+        assert_eq!(
+            validate_protocol_definitions(SAMPLE_SCHEMA).unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn duplicate_stream_key_is_reported() {
+        let schema =
+            "# Header\n---\n# A\nMAIN_STREAMS:\n  pt1; a\n---\n# B\nMAIN_STREAMS:\n  pt1; b\n";
+        assert_eq!(
+            validate_protocol_definitions(schema).unwrap(),
+            vec![Warning {
+                section: "B".to_string(),
+                kind: WarningKind::DuplicateStream("pt1".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn empty_section_is_reported() {
+        let schema = "# Header\n---\n# A\nMAIN_STREAMS:\n  pt1; a\n---\n# EMPTY\n---\n";
+        assert_eq!(
+            validate_protocol_definitions(schema).unwrap(),
+            vec![Warning {
+                section: "EMPTY".to_string(),
+                kind: WarningKind::EmptySection,
+            }]
+        );
+    }
+
+    #[test]
+    fn misspelled_block_label_is_reported() {
+        let schema =
+            "# Header\n---\n# A\nMAIN_STREAM:\n  pt1; a\n---\n# B\nMAIN_STREAMS:\n  pt2; b\n";
+        let unknown = Warning {
+            section: "A".to_string(),
+            kind: WarningKind::UnknownBlock("MAIN_STREAM".to_string()),
+        };
+        assert_eq!(
+            validate_protocol_definitions(schema).unwrap(),
+            vec![unknown]
+        );
+        let err = load_protocol_definitions(schema, ValidationMode::Strict).unwrap_err();
+        assert!(
+            err.to_string().contains("unknown block 'MAIN_STREAM:'"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn schema_without_preamble_keeps_first_section() {
+        let schema = "---\n# POINTS\nMAIN_STREAMS:\n  pt1; a\n---\n# HL\nMAIN_STREAMS:\n  hl1; b\n";
+        assert_eq!(validate_protocol_definitions(schema).unwrap(), vec![]);
+        let (definitions, _) = parse_protocol(schema).unwrap();
+        let names: Vec<_> = definitions.iter().map(|def| def.name.as_str()).collect();
+        assert_eq!(names, ["POINTS", "HL"]);
+    }
+
+    #[test]
+    fn section_before_first_separator_is_not_a_preamble() {
+        let schema = "# POINTS\nMAIN_STREAMS:\n  pt1; a\n---\n# HL\nMAIN_STREAMS:\n  hl1; b\n";
+        let (definitions, _) = parse_protocol(schema).unwrap();
+        assert_eq!(definitions.len(), 2);
+    }
+
+    #[test]
+    fn sample_schema_captures_descriptions() {
+        let (definitions, _) = parse_protocol(SAMPLE_SCHEMA).unwrap();
+        let points = definitions.iter().find(|def| def.name == "POINTS").unwrap();
+        assert_eq!(
+            points.stream_descriptions["pt1"],
+            "Main stream for athlete 1"
+        );
+        assert_eq!(points.required_argument_descriptions["1"], "Punch point");
+        assert_eq!(points.main_streams, ["pt1", "pt2"]);
+    }
+
+    #[test]
+    fn required_and_optional_arguments_keep_their_own_descriptions() {
+        let (definitions, _) = parse_protocol(SAMPLE_SCHEMA).unwrap();
+        let challenge = definitions
+            .iter()
+            .find(|def| def.main_streams.iter().any(|code| code == "ch0"))
+            .unwrap();
+        assert_eq!(
+            challenge.required_argument_descriptions["1"],
+            "Boolean if challenge is accepted or denied. -1 for canceled challenge"
+        );
+        assert_eq!(
+            challenge.optional_argument_descriptions["1"],
+            "Boolean if the accepted challenge is won or lost"
+        );
+    }
+
+    #[test]
+    fn first_description_wins_within_a_block() {
+        let schema = "# A\nMAIN_STREAMS:\n  pt1; a\nREQUIRED_ARGUMENTS:\n  1; first\n  1; second\n";
+        let (definitions, _) = parse_protocol(schema).unwrap();
+        assert_eq!(definitions[0].required_argument_descriptions["1"], "first");
+    }
+
+    #[test]
+    fn delimiter_variants_parse_consistently() {
+        for raw in ["pt1;3;", "pt1;3", "pt1;;3;", ";pt1;3;", " pt1 ; 3 ;\r\n"] {
+            assert_eq!(
+                parse_udp_message(raw).unwrap(),
+                message("pt1", &["3"]),
+                "{}",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn empty_fields_collapse_in_positional_streams() {
+        assert_eq!(
+            parse_udp_message("at1;N. D;;MRN;").unwrap(),
+            message("at1", &["N. D", "MRN"])
+        );
+    }
+
+    #[test]
+    fn message_without_stream_is_rejected() {
+        assert!(parse_udp_message("").is_err());
+        assert!(parse_udp_message(" ;; ").is_err());
+    }
+
+    #[test]
+    fn binary_frames_round_trip() {
+        for raw in [
+            "pt1;3;",
+            "ch0;-1;",
+            "clk;1:59;start;",
+            "brk;03;",
+            "wmh;Nicolas DESMOND;2-0 PTF;",
+            "ij0;",
+        ] {
+            let original = parse_udp_message(raw).unwrap();
+            let frame = encode_binary(&original).unwrap();
+            assert_eq!(decode_binary(&frame).unwrap(), original, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn binary_frame_layout() {
+        let frame = encode_binary(&message("pt1", &["3", "x"])).unwrap();
+        assert_eq!(frame, [0, 9, 0, TAG_INT, 0, 0, 0, 3, TAG_TEXT, 1, b'x']);
+    }
+
+    #[test]
+    fn malformed_binary_frames_are_rejected() {
+        assert!(encode_binary(&message("zzz", &[])).is_err());
+        assert!(decode_binary(&[0]).is_err());
+        assert!(decode_binary(&[0, 2, 0]).is_err());
+        assert!(decode_binary(&[0, 1, 200]).is_err());
+        assert!(decode_binary(&[0, 3, 0, TAG_INT, 0]).is_err());
+        assert!(decode_binary(&[0, 2, 0, 0x7f]).is_err());
+    }
+
+    #[test]
+    fn each_source_decodes_with_its_configured_format() {
+        let expected = message("hl1", &["50"]);
+        let binary = encode_binary(&expected).unwrap();
+        assert_eq!(
+            decode_datagram(&binary, WireFormat::Binary).unwrap(),
+            expected
+        );
+        assert_eq!(
+            decode_datagram(b"hl1;50;", WireFormat::Text).unwrap(),
+            expected
+        );
+        assert!(decode_datagram(b"hl1;50;", WireFormat::Binary).is_err());
+    }
+
+    #[test]
+    fn full_winner_rounds_payload() {
+        let rounds = parse_winner_rounds(&parse_udp_message("wrd;rd1;2;rd2;1;rd3;0").unwrap());
+        assert_eq!(
+            rounds.unwrap(),
+            [
+                RoundWinner {
+                    round: 1,
+                    winner: Some(2)
+                },
+                RoundWinner {
+                    round: 2,
+                    winner: Some(1)
+                },
+                RoundWinner {
+                    round: 3,
+                    winner: None
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_winner_rounds_payload() {
+        let rounds = parse_winner_rounds(&parse_udp_message("wrd;rd1;1;").unwrap());
+        assert_eq!(
+            rounds.unwrap(),
+            [RoundWinner {
+                round: 1,
+                winner: Some(1)
+            }]
+        );
+    }
+
+    #[test]
+    fn invalid_winner_rounds_payloads_are_rejected() {
+        for raw in [
+            "wrd;",
+            "wrd;rd1",
+            "wrd;rd1;1;rd1;2",
+            "wrd;rd0;1",
+            "wrd;rd1;3",
+            "pt1;3",
+        ] {
+            let message = parse_udp_message(raw).unwrap();
+            assert!(parse_winner_rounds(&message).is_err(), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn to_wire_round_trips_parsed_messages() {
+        for raw in [
+            "pt1;3;",
+            "pt1;;3",
+            "clk;1:59;start",
+            "wmh;Nicolas DESMOND;2-0 PTF;",
+            "ch0;",
+        ] {
+            let parsed = parse_udp_message(raw).unwrap();
+            assert_eq!(
+                parse_udp_message(&parsed.to_wire()).unwrap(),
+                parsed,
+                "{}",
+                raw
+            );
+        }
+        assert_eq!(message("pt1", &["3"]).to_wire(), "pt1;3;");
+    }
+
+    #[test]
+    fn send_reaches_listening_socket() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let sent = message("pt1", &["3"]);
+
+        send_udp_message(&sender, receiver.local_addr().unwrap(), &sent).unwrap();
+
+        let mut buf = [0u8; 64];
+        let (len, _) = receiver.recv_from(&mut buf).unwrap();
+        let received = parse_udp_message(std::str::from_utf8(&buf[..len]).unwrap()).unwrap();
+        assert_eq!(received, sent);
+    }
+
+    #[test]
+    fn bind_retry_succeeds_once_the_port_is_released() {
+        let holder = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = holder.local_addr().unwrap();
+        let release = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            drop(holder);
+        });
+
+        let socket = bind_with_retry(addr, 50, Duration::from_millis(20)).unwrap();
+        assert_eq!(socket.local_addr().unwrap(), addr);
+        release.join().unwrap();
+    }
+
+    #[test]
+    fn bind_retry_gives_up_after_the_last_attempt() {
+        let holder = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = holder.local_addr().unwrap();
+        assert!(bind_with_retry(addr, 2, Duration::from_millis(1)).is_err());
+    }
+
+    #[test]
+    fn send_keeps_empty_middle_arguments() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let sent = message("at1", &["N. D", "", "MRN"]);
+
+        send_udp_message(&sender, receiver.local_addr().unwrap(), &sent).unwrap();
+
+        let mut buf = [0u8; 64];
+        let (len, _) = receiver.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"at1;N. D;;MRN;");
+    }
+
+    #[test]
+    fn send_rejects_malformed_fields() {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        for bad in [
+            message("", &[]),
+            message(" pt1 ", &["3"]),
+            message("pt1", &["3", ""]),
+            message("pt1", &["3 "]),
+            message("pt1", &["3;4"]),
+        ] {
+            assert!(send_udp_message(&socket, addr, &bad).is_err(), "{:?}", bad);
+        }
+    }
+
+    const INVALID_SCHEMA: &str = concat!(
+        "# Header\n---\n",
+        "# A\nMAIN_STREAMS:\n  pt1; a\n---\n",
+        "# B\nMAIN_STREAMS:\n  pt1; dup\n  pt2; x\n---\n",
+        "# EMPTY\n---\n",
+    );
+
+    #[test]
+    fn lenient_mode_loads_valid_sections_and_returns_warnings() {
+        let (definitions, warnings) =
+            load_protocol_definitions(INVALID_SCHEMA, ValidationMode::Lenient).unwrap();
+        let names: Vec<_> = definitions.iter().map(|def| def.name.as_str()).collect();
+        assert_eq!(names, ["A", "B"]);
+        assert_eq!(definitions[1].main_streams, ["pt2"]);
+        assert!(!definitions[1].stream_descriptions.contains_key("pt1"));
+        assert_eq!(definitions[1].stream_descriptions["pt2"], "x");
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn duplicate_within_a_section_keeps_its_description() {
+        let schema = "# A\nMAIN_STREAMS:\n  pt1; a\n  pt1; again\n";
+        let (definitions, warnings) =
+            load_protocol_definitions(schema, ValidationMode::Lenient).unwrap();
+        assert_eq!(definitions[0].main_streams, ["pt1"]);
+        assert_eq!(definitions[0].stream_descriptions["pt1"], "a");
+        assert_eq!(
+            warnings,
+            [Warning {
+                section: "A".to_string(),
+                kind: WarningKind::DuplicateStream("pt1".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn strict_mode_fails_on_the_same_input() {
+        let err = load_protocol_definitions(INVALID_SCHEMA, ValidationMode::Strict).unwrap_err();
+        assert!(
+            err.to_string().contains("duplicate stream key 'pt1'"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn lenient_mode_fails_when_no_definition_survives() {
+        let schema = "# Header\n---\n# EMPTY\n---\n# D\nEXAMPLES:\n  x;\n";
+        assert!(load_protocol_definitions(schema, ValidationMode::Lenient).is_err());
+        assert_eq!(validate_protocol_definitions(schema).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn point_with_type_parses() {
+        let parsed = parse_points(&parse_udp_message("pt1;3;").unwrap()).unwrap();
+        assert_eq!(parsed, (1, PointType::Head));
+        let parsed = parse_points(&parse_udp_message("pt2;5").unwrap()).unwrap();
+        assert_eq!(parsed, (2, PointType::TechnicalHead));
+    }
+
+    #[test]
+    fn point_without_type_is_an_error() {
+        for raw in ["pt1;", "pt1;;"] {
+            let err = parse_points(&parse_udp_message(raw).unwrap()).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "protocol error: pt1: missing point type",
+                "{}",
+                raw
+            );
+        }
+        let err = parse_points(&message("pt1", &[""])).unwrap_err();
+        assert_eq!(err.to_string(), "protocol error: pt1: missing point type");
+        assert!(parse_points(&parse_udp_message("pt1;9;").unwrap()).is_err());
+        assert!(parse_points(&parse_udp_message("hl1;50;").unwrap()).is_err());
+    }
+
+    #[test]
+    fn rewrite_rules_normalize_stream_before_dispatch() {
+        let rules = parse_rewrite_rules("# legacy consoles\nPT1 -> pt1\n\npt1 -> pt2\n").unwrap();
+        let rewritten = apply_rewrite_rules(parse_udp_message("PT1;3;").unwrap(), &rules);
+        assert_eq!(rewritten, message("pt1", &["3"]));
+        assert_eq!(parse_points(&rewritten).unwrap(), (1, PointType::Head));
+    }
+
+    #[test]
+    fn unmatched_message_passes_through_unchanged() {
+        let rules = parse_rewrite_rules("PT1 -> pt1").unwrap();
+        let original = parse_udp_message("hl1;50;").unwrap();
+        assert_eq!(apply_rewrite_rules(original.clone(), &rules), original);
+    }
+
+    #[test]
+    fn malformed_rewrite_rules_are_rejected() {
+        for content in ["PT1 ->", "-> pt1", "PT1 pt1", "a -> b -> c"] {
+            assert!(parse_rewrite_rules(content).is_err(), "{}", content);
+        }
+    }
 }